@@ -13,6 +13,19 @@ pub struct CLIArgs {
 pub enum Command {
     #[command(name = "man")]
     Manager,
+    #[command(
+        name = "self-update",
+        about = "Update the apm binary in place from GitHub releases"
+    )]
+    SelfUpdate {
+        #[arg(long = "no-confirm", help = "Skip the confirmation prompt")]
+        no_confirm: bool,
+        #[arg(
+            long = "version",
+            help = "Pin to a specific release tag instead of the latest"
+        )]
+        version: Option<String>,
+    },
     #[command(name = "mod", about = "Modify an existing package")]
     Modifier {
         #[command(subcommand)]
@@ -40,6 +53,72 @@ pub enum Command {
     },
 }
 
+#[derive(ValueEnum, PartialEq, Debug, Hash, Clone, Copy)]
+pub enum CompressionMethodArg {
+    Stored,
+    Deflated,
+    Bzip2,
+    Zstd,
+}
+
+impl From<CompressionMethodArg> for zip::CompressionMethod {
+    fn from(method: CompressionMethodArg) -> Self {
+        return match method {
+            CompressionMethodArg::Stored => zip::CompressionMethod::Stored,
+            CompressionMethodArg::Deflated => zip::CompressionMethod::Deflated,
+            CompressionMethodArg::Bzip2 => zip::CompressionMethod::Bzip2,
+            CompressionMethodArg::Zstd => zip::CompressionMethod::Zstd,
+        };
+    }
+}
+
+impl CompressionMethodArg {
+    /// Build the library [`CompressionConfig`] the `MakePackage` handler threads
+    /// into `compress_directory` from the `--method`/`--level` flags.
+    ///
+    /// [`CompressionConfig`]: archer_package_manager::zip_manipulation::CompressionConfig
+    pub fn into_config(
+        self,
+        level: Option<i32>,
+    ) -> archer_package_manager::zip_manipulation::CompressionConfig {
+        return archer_package_manager::zip_manipulation::CompressionConfig {
+            method: self.into(),
+            level,
+        };
+    }
+}
+
+#[derive(ValueEnum, PartialEq, Debug, Hash, Clone, Copy)]
+pub enum SymlinkModeArg {
+    Error,
+    Skip,
+    Follow,
+}
+
+impl From<SymlinkModeArg> for archer_package_manager::zip_manipulation::SymlinkMode {
+    fn from(mode: SymlinkModeArg) -> Self {
+        return match mode {
+            SymlinkModeArg::Error => Self::Error,
+            SymlinkModeArg::Skip => Self::Skip,
+            SymlinkModeArg::Follow => Self::Follow,
+        };
+    }
+}
+
+impl Display for SymlinkModeArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "{}",
+            match self {
+                SymlinkModeArg::Error => "error",
+                SymlinkModeArg::Skip => "skip",
+                SymlinkModeArg::Follow => "follow",
+            }
+        );
+    }
+}
+
 #[derive(ValueEnum, PartialEq, Debug, Hash, Clone, Copy)]
 pub enum ExportDataFormat {
     Readable,
@@ -75,6 +154,12 @@ pub enum ModiferOperation {
         path: Option<String>,
         #[arg(short, help = "The path to the output zip file")]
         output_path: Option<String>,
+        #[arg(
+            long = "stdout",
+            help = "Write the resulting package to stdout instead of a file",
+            conflicts_with = "output_path"
+        )]
+        stdout: bool,
         #[arg(short, long, help = "Show verbose output")]
         verbose: bool,
     },
@@ -101,6 +186,12 @@ pub enum ModiferOperation {
         path: Option<String>,
         #[arg(short, help = "The path to the output zip file")]
         output_path: Option<String>,
+        #[arg(
+            long = "stdout",
+            help = "Write the resulting package to stdout instead of a file",
+            conflicts_with = "output_path"
+        )]
+        stdout: bool,
     },
     #[command(
         short_flag = 'm',
@@ -132,6 +223,44 @@ pub enum ModiferOperation {
             help = "Specify the output path for the package"
         )]
         output_path: Option<String>,
+        #[arg(
+            long = "stdout",
+            help = "Write the resulting package to stdout instead of a file",
+            conflicts_with = "output_path"
+        )]
+        stdout: bool,
+        #[arg(short, long, help = "Show verbose output")]
+        verbose: bool,
+        #[arg(
+            long = "method",
+            help = "The compression method to use for each entry",
+            default_value_t = CompressionMethodArg::Deflated
+        )]
+        method: CompressionMethodArg,
+        #[arg(
+            long = "level",
+            help = "The numeric compression level, method dependent"
+        )]
+        level: Option<i32>,
+        #[arg(
+            long = "symlinks",
+            help = "How to handle symlinks encountered in the directory",
+            default_value_t = SymlinkModeArg::Error
+        )]
+        symlinks: SymlinkModeArg,
+    },
+    #[command(
+        long_flag = "append",
+        about = "Append files to an existing package without rebuilding it"
+    )]
+    Append {
+        #[arg(help = "The path to the archer zip file to append to")]
+        archive: String,
+        #[arg(
+            required = true,
+            help = "The files to append, as source paths (archived under their file name)"
+        )]
+        files: Vec<String>,
         #[arg(short, long, help = "Show verbose output")]
         verbose: bool,
     },
@@ -218,6 +347,21 @@ impl Default for ExportDataFormat {
     }
 }
 
+impl Display for CompressionMethodArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "{}",
+            match self {
+                CompressionMethodArg::Stored => "stored",
+                CompressionMethodArg::Deflated => "deflated",
+                CompressionMethodArg::Bzip2 => "bzip2",
+                CompressionMethodArg::Zstd => "zstd",
+            }
+        );
+    }
+}
+
 impl Display for ExportDataFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         return write!(
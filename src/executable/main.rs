@@ -1,11 +1,13 @@
 mod cli;
 mod modifier;
+mod self_update;
 mod util;
 
 use clap::Parser;
 
 use cli::{CLIArgs, Command};
 use modifier::execute_modifier_op;
+use self_update::execute_self_update;
 
 fn main() {
     let args = CLIArgs::parse();
@@ -13,5 +15,9 @@ fn main() {
     match args.command {
         Command::Manager => eprintln!("Error: Manager is not enabled."),
         Command::Modifier { operation } => execute_modifier_op(operation),
+        Command::SelfUpdate {
+            no_confirm,
+            version,
+        } => execute_self_update(no_confirm, version),
     }
 }
@@ -0,0 +1,220 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use archer_package_manager::zip_manipulation::{extract_file_from_archive, read_archive_from_bytes};
+
+/// The GitHub releases endpoint queried for newer builds of `apm`.
+const RELEASES_URL: &str = "https://api.github.com/repos/amalojer-wk/archer-pm/releases";
+
+/// The name of the binary entry extracted from a downloaded release asset.
+const BINARY_NAME: &str = "apm";
+
+pub fn execute_self_update(no_confirm: bool, version: Option<String>) {
+    if let Err(e) = run_self_update(no_confirm, version) {
+        eprintln!("Error: {}", e);
+    }
+}
+
+fn run_self_update(no_confirm: bool, version: Option<String>) -> Result<(), String> {
+    // A pinned tag is fetched directly so it works even for releases that have
+    // scrolled off the first page of the (paginated) releases list.
+    let selected = match &version {
+        Some(tag) => fetch_release_by_tag(tag)?,
+        None => pick_latest(fetch_releases()?)?,
+    };
+
+    let current = env!("CARGO_PKG_VERSION");
+    if version.is_none() && !is_newer(&selected.tag_name, current) {
+        println!("Already up to date (running {}).", current);
+        return Ok(());
+    }
+
+    if !no_confirm && !confirm(&selected.tag_name, current)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let asset = select_asset(&selected)?;
+    let bytes = download(&asset.browser_download_url)?;
+
+    // Release assets for this crate are themselves zips; pull the binary out.
+    let mut archive = read_archive_from_bytes(&bytes).map_err(|e| e.to_string())?;
+    let binary =
+        extract_file_from_archive(&mut archive, BINARY_NAME).map_err(|e| e.to_string())?;
+
+    swap_executable(&binary)?;
+
+    println!("Updated to {}.", selected.tag_name);
+    return Ok(());
+}
+
+/// Write the new binary to a temp file beside the running executable, mark it
+/// executable, move the current binary aside, then rename the new one into
+/// place. Renaming the old binary away first means a failed swap leaves the
+/// original intact.
+fn swap_executable(binary: &[u8]) -> Result<(), String> {
+    let current_exe =
+        env::current_exe().map_err(|e| format!("Could not locate current exe: {}", e))?;
+    let dir = current_exe
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let temp_path = dir.join(".apm-update.tmp");
+    let mut temp = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&temp_path)
+        .map_err(|e| format!("Could not open temp file: {}", e))?;
+    temp.write_all(binary)
+        .map_err(|e| format!("Could not write temp file: {}", e))?;
+
+    set_executable(&temp_path)?;
+
+    let backup_path = dir.join(".apm-update.old");
+    fs::rename(&current_exe, &backup_path)
+        .map_err(|e| format!("Could not move current binary aside: {}", e))?;
+
+    if let Err(e) = fs::rename(&temp_path, &current_exe) {
+        // Restore the original binary on failure.
+        let _ = fs::rename(&backup_path, &current_exe);
+        return Err(format!("Could not install new binary: {}", e));
+    }
+
+    let _ = fs::remove_file(&backup_path);
+    return Ok(());
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = fs::metadata(path)
+        .map_err(|e| e.to_string())?
+        .permissions();
+    perms.set_mode(0o755);
+    return fs::set_permissions(path, perms).map_err(|e| e.to_string());
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), String> {
+    return Ok(());
+}
+
+/// The release with the highest tag. It is not compared with the compiled-in
+/// version here; the caller does that.
+fn pick_latest(releases: Vec<Release>) -> Result<Release, String> {
+    return releases
+        .into_iter()
+        .max_by(|a, b| compare_tags(&a.tag_name, &b.tag_name))
+        .ok_or_else(|| "No releases available".to_string());
+}
+
+fn select_asset(release: &Release) -> Result<&Asset, String> {
+    // Prefer an asset that names both this platform's OS and architecture;
+    // failing that, take the first asset that names the OS, without checking
+    // which architecture its name encodes. With no asset naming the OS, fail.
+    let os = env::consts::OS;
+    let arch = env::consts::ARCH;
+    release
+        .assets
+        .iter()
+        .find(|a| a.name.contains(os) && a.name.contains(arch))
+        .or_else(|| release.assets.iter().find(|a| a.name.contains(os)))
+        .ok_or_else(|| {
+            format!(
+                "Release {} has no asset matching this platform ({}-{})",
+                release.tag_name, os, arch
+            )
+        })
+}
+
+fn confirm(tag: &str, current: &str) -> Result<bool, String> {
+    use std::io::BufRead;
+
+    print!("Update apm from {} to {}? [y/N] ", current, tag);
+    std::io::stdout().flush().map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+
+    return Ok(matches!(line.trim(), "y" | "Y" | "yes"));
+}
+
+fn fetch_releases() -> Result<Vec<Release>, String> {
+    let body = http_get(RELEASES_URL)?;
+    return serde_json::from_slice(&body).map_err(|e| format!("Could not parse releases: {}", e));
+}
+
+/// Fetch a single release by its tag via the `releases/tags/<tag>` endpoint,
+/// which resolves any tag regardless of how many releases exist.
+fn fetch_release_by_tag(tag: &str) -> Result<Release, String> {
+    let url = format!("{}/tags/{}", RELEASES_URL, tag);
+    let body = http_get(&url).map_err(|_| format!("No release found for version {}", tag))?;
+    return serde_json::from_slice(&body)
+        .map_err(|e| format!("Could not parse release {}: {}", tag, e));
+}
+
+fn download(url: &str) -> Result<Vec<u8>, String> {
+    return http_get(url);
+}
+
+fn http_get(url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwest::blocking::Client::builder()
+        .user_agent(concat!("apm/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())?
+        .get(url)
+        .send()
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+
+    return response
+        .bytes()
+        .map(|b| b.to_vec())
+        .map_err(|e| e.to_string());
+}
+
+/// Parse a `vX.Y.Z` tag into comparable `(major, minor, patch)` components,
+/// treating anything unparseable as `0`.
+fn parse_semver(tag: &str) -> (u64, u64, u64) {
+    let trimmed = normalize_tag(tag);
+    let mut parts = trimmed.split('.').map(|p| p.parse::<u64>().unwrap_or(0));
+    return (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    );
+}
+
+fn normalize_tag(tag: &str) -> String {
+    return tag.trim().trim_start_matches('v').to_string();
+}
+
+fn is_newer(tag: &str, current: &str) -> bool {
+    return parse_semver(tag) > parse_semver(current);
+}
+
+fn compare_tags(a: &str, b: &str) -> std::cmp::Ordering {
+    return parse_semver(a).cmp(&parse_semver(b));
+}
+
+#[derive(serde::Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(serde::Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
@@ -1,19 +1,115 @@
 use crate::error::{APMError, APMErrorType};
 
-use std::fs::{File, OpenOptions};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
 use std::io::{copy, Cursor, Read, Seek, Write};
 use std::path::{Path, PathBuf};
 
 use walkdir::WalkDir;
-use zip::{write::FileOptions, ZipArchive, ZipWriter};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Compression settings applied to every entry written into a package.
+///
+/// `method` selects the zip compression algorithm and `level` is the
+/// algorithm specific numeric level (`None` uses the method's default). These
+/// map straight onto the `FileOptions` handed to `start_file`, mirroring the
+/// per-entry controls the `zip` CLI exposes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressionConfig {
+    pub method: CompressionMethod,
+    pub level: Option<i32>,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        return Self {
+            method: CompressionMethod::Deflated,
+            level: None,
+        };
+    }
+}
+
+impl CompressionConfig {
+    fn to_file_options(self) -> FileOptions {
+        return FileOptions::default()
+            .compression_method(self.method)
+            .compression_level(self.level);
+    }
+}
+
+/// How `compress_directory` treats symlinks encountered while walking the
+/// source tree.
+///
+/// `Error` aborts the run (the historical behaviour), `Skip` silently omits the
+/// link (recording it in the tracked file names), and `Follow` resolves the
+/// target via `fs::canonicalize` and archives its contents under the link's
+/// stripped name, refusing to revisit a canonical path so self-referential
+/// links can't loop forever.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymlinkMode {
+    Error,
+    Skip,
+    Follow,
+}
+
+impl Default for SymlinkMode {
+    fn default() -> Self {
+        return Self::Error;
+    }
+}
+
+/// Below this many files the serial path is used: spinning up a worker pool
+/// costs more than it saves for small packages.
+const PARALLEL_FILE_THRESHOLD: usize = 64;
 
 pub fn compress_directory(
     path: &str,
     track_file_names: bool,
     dont_strip_base_dir: bool,
+    compression: CompressionConfig,
+    symlink_mode: SymlinkMode,
+) -> Result<(Vec<u8>, Option<Vec<(String, String)>>), APMError> {
+    // The parallel pipeline produces raw Deflate streams, so it only applies to
+    // Deflated packages; `Follow` needs the recursive serial walk to resolve
+    // link targets. Everything else falls back to the serial path, which also
+    // handles small trees where threading wouldn't pay off.
+    let file_count = WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .count();
+
+    if compression.method != CompressionMethod::Deflated
+        || symlink_mode == SymlinkMode::Follow
+        || file_count < PARALLEL_FILE_THRESHOLD
+    {
+        return compress_directory_serial(
+            path,
+            track_file_names,
+            dont_strip_base_dir,
+            compression,
+            symlink_mode,
+        );
+    }
+
+    return compress_directory_parallel(
+        path,
+        track_file_names,
+        dont_strip_base_dir,
+        compression,
+        symlink_mode,
+    );
+}
+
+fn compress_directory_serial(
+    path: &str,
+    track_file_names: bool,
+    dont_strip_base_dir: bool,
+    compression: CompressionConfig,
+    symlink_mode: SymlinkMode,
 ) -> Result<(Vec<u8>, Option<Vec<(String, String)>>), APMError> {
     let mut buffer = Vec::new();
-    let options = FileOptions::default();
+    let options = compression.to_file_options();
     let mut zip_writer = ZipWriter::new(Cursor::new(&mut buffer));
     let mut file_names = {
         if track_file_names {
@@ -22,6 +118,7 @@ pub fn compress_directory(
             None
         }
     };
+    let mut visited: HashSet<PathBuf> = HashSet::new();
 
     for entry in WalkDir::new(path).into_iter() {
         let entry = entry.map_err(|e| APMErrorType::WalkdirError.into_apm_error(e.to_string()))?;
@@ -44,10 +141,30 @@ pub fn compress_directory(
         };
 
         if entry.file_type().is_symlink() {
-            return Err(APMErrorType::SymlinkFoundError.into_apm_error(format!(
-                "Found symlink at path {}\nSymlinks cannot be compressed.",
-                entry.file_name().to_str().unwrap_or("PATH_UNKNOWN")
-            )));
+            match symlink_mode {
+                SymlinkMode::Error => {
+                    return Err(APMErrorType::SymlinkFoundError.into_apm_error(format!(
+                        "Found symlink at path {}\nSymlinks cannot be compressed.",
+                        entry.file_name().to_str().unwrap_or("PATH_UNKNOWN")
+                    )));
+                }
+                SymlinkMode::Skip => {
+                    if let Some(file_names) = &mut file_names {
+                        file_names.push((name, stripped_file_name));
+                    }
+                    continue;
+                }
+                SymlinkMode::Follow => {
+                    follow_symlink(
+                        &mut zip_writer,
+                        entry.path(),
+                        &stripped_file_name,
+                        options,
+                        &mut visited,
+                        &mut file_names,
+                    )?;
+                }
+            }
         } else if entry.file_type().is_dir() {
             zip_writer
                 .add_directory(&stripped_file_name, options)
@@ -74,6 +191,312 @@ pub fn compress_directory(
     return Ok((buffer, file_names));
 }
 
+/// What a tuple gathered during the cheap walk pass represents, so the writer
+/// pass can reproduce the serial path's walk-order `file_names` output.
+enum EntryKind {
+    Directory,
+    File,
+    SkippedSymlink,
+}
+
+/// A file entry collected during the cheap walk pass, compressed off-thread.
+struct PendingEntry {
+    absolute: String,
+    stripped: String,
+}
+
+/// The result of compressing a single [`PendingEntry`]: its entry name plus a
+/// one-entry zip archive holding the finished stream. The writer raw-copies
+/// that entry into the final archive, so the compression work stays off the
+/// writer thread.
+struct CompressedEntry {
+    stripped: String,
+    archive: Vec<u8>,
+}
+
+/// Concurrent variant of [`compress_directory_serial`].
+///
+/// A cheap [`WalkDir`] pass records the tree in walk order as `(absolute,
+/// stripped, kind)` tuples, where `kind` is an [`EntryKind`]. File entries are fanned out to a scoped worker
+/// pool that reads and Deflate-compresses each one independently, while a
+/// single writer walks the original order and raw-copies the precompressed file
+/// entries. Emitting in walk order keeps the central directory deterministic
+/// regardless of which worker finishes first.
+fn compress_directory_parallel(
+    path: &str,
+    track_file_names: bool,
+    dont_strip_base_dir: bool,
+    compression: CompressionConfig,
+    symlink_mode: SymlinkMode,
+) -> Result<(Vec<u8>, Option<Vec<(String, String)>>), APMError> {
+    let mut buffer = Vec::new();
+    let options = compression.to_file_options();
+    let mut zip_writer = ZipWriter::new(Cursor::new(&mut buffer));
+    let mut file_names = if track_file_names {
+        Some(Vec::new())
+    } else {
+        None
+    };
+
+    // Cheap pass: collect the tree in deterministic walk order.
+    let mut entries: Vec<(String, String, EntryKind)> = Vec::new();
+    for entry in WalkDir::new(path).into_iter() {
+        let entry = entry.map_err(|e| APMErrorType::WalkdirError.into_apm_error(e.to_string()))?;
+
+        let name = entry.path().display().to_string();
+        if name == path {
+            continue;
+        }
+
+        let stripped_file_name = if dont_strip_base_dir {
+            name.clone()
+        } else {
+            entry
+                .path()
+                .strip_prefix(path)
+                .map(|p| p.display().to_string())
+                .unwrap_or(name.clone())
+        };
+
+        if entry.file_type().is_symlink() {
+            // `Follow` never reaches this path; handle `Error`/`Skip` only.
+            match symlink_mode {
+                SymlinkMode::Skip => {
+                    // Carry the skip through the writer pass so it lands in
+                    // `file_names` interleaved in walk order, matching serial.
+                    entries.push((name, stripped_file_name, EntryKind::SkippedSymlink));
+                    continue;
+                }
+                _ => {
+                    return Err(APMErrorType::SymlinkFoundError.into_apm_error(format!(
+                        "Found symlink at path {}\nSymlinks cannot be compressed.",
+                        entry.file_name().to_str().unwrap_or("PATH_UNKNOWN")
+                    )));
+                }
+            }
+        }
+
+        let kind = if entry.file_type().is_dir() {
+            EntryKind::Directory
+        } else {
+            EntryKind::File
+        };
+        entries.push((name, stripped_file_name, kind));
+    }
+
+    // Fan the file entries out, compressing each one on a worker thread.
+    let pending: Vec<PendingEntry> = entries
+        .iter()
+        .filter(|(_, _, kind)| matches!(kind, EntryKind::File))
+        .map(|(absolute, stripped, _)| PendingEntry {
+            absolute: absolute.clone(),
+            stripped: stripped.clone(),
+        })
+        .collect();
+
+    // Feed the file entries through a bounded pool: a fixed number of workers
+    // drain a shared queue, so the thread count stays constant no matter how
+    // many files the package holds.
+    let worker_count = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(pending.len().max(1));
+    let (tx, rx) = crossbeam::channel::unbounded::<PendingEntry>();
+    for entry in pending {
+        let _ = tx.send(entry);
+    }
+    drop(tx);
+
+    let compressed: Vec<CompressedEntry> = crossbeam::thread::scope(|scope| {
+        let handles: Vec<_> = (0..worker_count)
+            .map(|_| {
+                let rx = rx.clone();
+                scope.spawn(move |_| {
+                    let mut out = Vec::new();
+                    while let Ok(entry) = rx.recv() {
+                        out.push(compress_entry(entry, compression));
+                    }
+                    out
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            match handle.join() {
+                Ok(batch) => results.extend(batch),
+                Err(_) => results.push(Err(APMErrorType::ZIPFileCopyError
+                    .into_apm_error("Compression worker panicked".to_string()))),
+            }
+        }
+        results.into_iter().collect::<Result<Vec<_>, APMError>>()
+    })
+    .map_err(|_| {
+        APMErrorType::ZIPFileCopyError.into_apm_error("Compression pool panicked".to_string())
+    })??;
+
+    // Index finished streams by entry name so the writer can pull them in order.
+    let mut by_name: std::collections::HashMap<String, CompressedEntry> = compressed
+        .into_iter()
+        .map(|c| (c.stripped.clone(), c))
+        .collect();
+
+    // Writer pass: emit directories and precompressed files in walk order.
+    for (absolute, stripped, kind) in entries {
+        match kind {
+            EntryKind::Directory => {
+                zip_writer.add_directory(&stripped, options).map_err(|e| {
+                    APMErrorType::ZIPAddDirectoryError.into_apm_error(e.to_string())
+                })?;
+            }
+            EntryKind::File => {
+                if let Some(entry) = by_name.remove(&stripped) {
+                    write_precompressed_entry(&mut zip_writer, &entry)?;
+                }
+            }
+            // Skipped symlinks produce no archive entry, only a tracked tuple.
+            EntryKind::SkippedSymlink => {}
+        }
+
+        if let Some(file_names) = &mut file_names {
+            file_names.push((absolute, stripped));
+        }
+    }
+
+    zip_writer
+        .finish()
+        .map_err(|e| APMErrorType::ZIPFinishError.into_apm_error(e.to_string()))?;
+
+    drop(zip_writer);
+
+    return Ok((buffer, file_names));
+}
+
+/// Compress a single file entry into a one-entry zip archive held in memory.
+///
+/// Reusing [`add_file_to_archive`] keeps the compression settings identical to
+/// the serial path; the resulting archive is raw-copied into the final package
+/// by [`write_precompressed_entry`].
+fn compress_entry(
+    entry: PendingEntry,
+    compression: CompressionConfig,
+) -> Result<CompressedEntry, APMError> {
+    let mut archive = Vec::new();
+    let mut writer = ZipWriter::new(Cursor::new(&mut archive));
+
+    add_file_to_archive(
+        &mut writer,
+        &entry.absolute,
+        &entry.stripped,
+        Some(compression.to_file_options()),
+    )?;
+
+    writer
+        .finish()
+        .map_err(|e| APMErrorType::ZIPFinishError.into_apm_error(e.to_string()))?;
+    drop(writer);
+
+    return Ok(CompressedEntry {
+        stripped: entry.stripped,
+        archive,
+    });
+}
+
+/// Raw-copy the single entry of a worker's in-memory archive into the final
+/// package, preserving its already-computed compression, CRC32, and sizes.
+fn write_precompressed_entry<A: Read + Seek + Write>(
+    zip_writer: &mut ZipWriter<A>,
+    entry: &CompressedEntry,
+) -> Result<(), APMError> {
+    let mut source = ZipArchive::new(Cursor::new(entry.archive.as_slice()))
+        .map_err(|e| APMErrorType::ZIPArchiveOpenError.into_apm_error(e.to_string()))?;
+    let file = source
+        .by_index(0)
+        .map_err(|e| APMErrorType::ZIPArchiveFileFindError.into_apm_error(e.to_string()))?;
+
+    zip_writer
+        .raw_copy_file(file)
+        .map_err(|e| APMErrorType::ZIPFileCopyError.into_apm_error(e.to_string()))?;
+
+    return Ok(());
+}
+
+/// Resolve a symlink and archive its target under `stripped_name`.
+///
+/// A file target is written as a single entry; a directory target is walked and
+/// its contents are archived beneath `stripped_name`. A link whose target can't
+/// be resolved — a directly self-referential link trips `ELOOP` in
+/// `fs::canonicalize` — is skipped rather than aborting the build. The canonical
+/// path of every top-level followed link is recorded in `visited`, so a link
+/// that resolves to an already-followed target is skipped rather than
+/// re-archived. Links encountered *inside* a followed directory are traversed
+/// via `WalkDir::follow_links`, which supplies its own loop detection for cycles
+/// reached transitively through the nested walk.
+fn follow_symlink<A: Read + Seek + Write>(
+    zip_writer: &mut ZipWriter<A>,
+    link_path: &Path,
+    stripped_name: &str,
+    options: FileOptions,
+    visited: &mut HashSet<PathBuf>,
+    file_names: &mut Option<Vec<(String, String)>>,
+) -> Result<(), APMError> {
+    // An unresolvable target — a directly self-referential link trips `ELOOP`
+    // here — is skipped rather than aborting the whole build.
+    let canonical = match fs::canonicalize(link_path) {
+        Ok(canonical) => canonical,
+        Err(_) => return Ok(()),
+    };
+
+    // Already followed this target: skip to avoid cyclic links looping.
+    if !visited.insert(canonical.clone()) {
+        return Ok(());
+    }
+
+    if canonical.is_dir() {
+        zip_writer
+            .add_directory(stripped_name, options)
+            .map_err(|e| APMErrorType::ZIPAddDirectoryError.into_apm_error(e.to_string()))?;
+
+        if let Some(file_names) = file_names {
+            file_names.push((canonical.display().to_string(), stripped_name.to_string()));
+        }
+
+        for entry in WalkDir::new(&canonical).min_depth(1).follow_links(true) {
+            let entry =
+                entry.map_err(|e| APMErrorType::WalkdirError.into_apm_error(e.to_string()))?;
+
+            let relative = entry
+                .path()
+                .strip_prefix(&canonical)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| entry.path().display().to_string());
+            let target_name = format!("{}/{}", stripped_name, relative);
+            let source = entry.path().display().to_string();
+
+            if entry.file_type().is_dir() {
+                zip_writer.add_directory(&target_name, options).map_err(|e| {
+                    APMErrorType::ZIPAddDirectoryError.into_apm_error(e.to_string())
+                })?;
+            } else {
+                add_file_to_archive(zip_writer, &source, &target_name, Some(options))?;
+            }
+
+            if let Some(file_names) = file_names {
+                file_names.push((source, target_name));
+            }
+        }
+    } else {
+        let source = canonical.display().to_string();
+        add_file_to_archive(zip_writer, &canonical, stripped_name, Some(options))?;
+
+        if let Some(file_names) = file_names {
+            file_names.push((source, stripped_name.to_string()));
+        }
+    }
+
+    return Ok(());
+}
+
 pub fn read_archive(path: &str) -> Result<ZipArchive<File>, APMError> {
     let f = OpenOptions::new()
         .read(true)
@@ -90,6 +513,16 @@ pub fn read_archive_from_bytes(bytes: &[u8]) -> Result<ZipArchive<Cursor<&[u8]>>
         .map_err(|e| APMErrorType::ZIPArchiveOpenError.into_apm_error(e.to_string()));
 }
 
+pub fn read_archive_from_stdin() -> Result<ZipArchive<Cursor<Vec<u8>>>, APMError> {
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut bytes)
+        .map_err(|e| APMErrorType::ZIPFileReadError.into_apm_error(e.to_string()))?;
+
+    return ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| APMErrorType::ZIPArchiveOpenError.into_apm_error(e.to_string()));
+}
+
 pub fn add_file_to_archive<A: Read + Seek + Write, P: AsRef<Path>>(
     archive: &mut ZipWriter<A>,
     file_path: P,
@@ -119,6 +552,37 @@ pub fn add_file_to_archive<A: Read + Seek + Write, P: AsRef<Path>>(
     return Ok(());
 }
 
+/// Append `files` to the package already on disk at `path` without recompressing
+/// its existing contents.
+///
+/// The archive is opened read+write and handed to `ZipWriter::new_append`, each
+/// `(source_path, entry_name)` pair is written with `add_file_to_archive`, and
+/// the central directory is rewritten when the writer is finished.
+pub fn append_to_archive(
+    path: &str,
+    files: &[(String, String)],
+    options: Option<FileOptions>,
+) -> Result<(), APMError> {
+    let handle = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| APMErrorType::FileOpenError.into_apm_error(e.to_string()))?;
+
+    let mut zip_writer = ZipWriter::new_append(handle)
+        .map_err(|e| APMErrorType::ZIPArchiveOpenError.into_apm_error(e.to_string()))?;
+
+    for (source_path, entry_name) in files {
+        add_file_to_archive(&mut zip_writer, source_path, entry_name, options)?;
+    }
+
+    zip_writer
+        .finish()
+        .map_err(|e| APMErrorType::ZIPFinishError.into_apm_error(e.to_string()))?;
+
+    return Ok(());
+}
+
 pub fn extract_file_from_archive<R: Read + Seek>(
     archive: &mut ZipArchive<R>,
     name: &str,